@@ -0,0 +1,153 @@
+// Local HTTP listener for providers that push odds updates via webhook instead of being polled.
+// Runs alongside the pull-based fetch_odds path so the app can react to line movement with
+// lower latency than a poll interval allows.
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::Value;
+use subtle::ConstantTimeEq;
+use tauri::async_runtime::JoinHandle;
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::secret;
+
+const SHARED_SECRET_HEADER: &str = "x-shared-secret";
+
+// The running listener task, plus the port it actually bound to (useful when `port` is 0 and
+// the OS picks an ephemeral one).
+#[derive(Default)]
+pub struct ListenerState(Mutex<Option<(JoinHandle<()>, u16)>>);
+
+// Constant-time comparison so a provider that can measure webhook response timing can't recover
+// the shared secret byte-by-byte.
+fn secrets_match(expected: &str, provided: &str) -> bool {
+    expected.as_bytes().len() == provided.as_bytes().len()
+        && expected.as_bytes().ct_eq(provided.as_bytes()).into()
+}
+
+async fn handle_request(
+    req: Request<Body>,
+    app_handle: AppHandle,
+    shared_secret: String,
+) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::POST || req.uri().path() != "/odds" {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap());
+    }
+
+    let provided = req
+        .headers()
+        .get(SHARED_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok());
+    let authorized = provided.is_some_and(|provided| secrets_match(&shared_secret, provided));
+    if !authorized {
+        return Ok(Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::empty())
+            .unwrap());
+    }
+
+    let body = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) => body,
+        Err(_) => {
+            return Ok(Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::empty())
+                .unwrap())
+        }
+    };
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(_) => {
+            return Ok(Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::empty())
+                .unwrap())
+        }
+    };
+
+    let _ = app_handle.emit_all("odds-push", payload);
+
+    Ok(Response::new(Body::empty()))
+}
+
+// Binds a tiny HTTP server to 127.0.0.1:`port` (pass 0 to let the OS pick a free port) and
+// returns the port it's actually listening on. Replaces any listener already running.
+//
+// `secret_name` is resolved through the `secret` module, the same way `auth_secret` is for
+// `fetch_odds` — the webhook's shared secret is stored once and referenced by name afterwards,
+// rather than being passed as a raw value over IPC on every call.
+#[command]
+pub async fn start_listener(
+    port: u16,
+    secret_name: String,
+    app_handle: AppHandle,
+    state: State<'_, ListenerState>,
+) -> Result<u16, String> {
+    let shared_secret = secret::resolve_blocking(secret_name.clone())
+        .await
+        .ok_or_else(|| format!("no secret stored for \"{}\"", secret_name))?;
+
+    let mut current = state.0.lock().map_err(|e| e.to_string())?;
+    if let Some((handle, _)) = current.take() {
+        handle.abort();
+    }
+
+    let addr: SocketAddr = ([127, 0, 0, 1], port).into();
+    let server = Server::try_bind(&addr).map_err(|e| e.to_string())?;
+    let bound_port = server.local_addr().port();
+
+    let make_svc = make_service_fn(move |_conn| {
+        let app_handle = app_handle.clone();
+        let shared_secret = shared_secret.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle_request(req, app_handle.clone(), shared_secret.clone())
+            }))
+        }
+    });
+
+    let handle = tauri::async_runtime::spawn(async move {
+        let _ = server.serve(make_svc).await;
+    });
+
+    *current = Some((handle, bound_port));
+    Ok(bound_port)
+}
+
+// Stops the running listener, if any.
+#[command]
+pub fn stop_listener(state: State<ListenerState>) -> Result<(), String> {
+    let mut current = state.0.lock().map_err(|e| e.to_string())?;
+    if let Some((handle, _)) = current.take() {
+        handle.abort();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_match_identical_values() {
+        assert!(secrets_match("webhook-secret", "webhook-secret"));
+    }
+
+    #[test]
+    fn secrets_match_rejects_different_values() {
+        assert!(!secrets_match("webhook-secret", "wrong-secret"));
+    }
+
+    #[test]
+    fn secrets_match_rejects_different_lengths() {
+        assert!(!secrets_match("webhook-secret", "webhook-secret-but-longer"));
+    }
+}
@@ -1,42 +1,501 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod listener;
+mod parse;
+mod secret;
+
 // Import necessary components for handling web requests and Tauri commands
-use tauri::command;
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager, State};
+use tauri::async_runtime::JoinHandle;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT};
 
-// This function will be callable from our frontend JavaScript
-#[command]
-async fn fetch_odds(url: String) -> Result<String, String> {
+// Everything the frontend needs to branch on a scrape attempt without guessing from the body:
+// the status (403/429 soft-blocks), where a redirect actually landed, and the response headers
+// (Content-Type, Retry-After, ...).
+#[derive(Clone, Serialize)]
+pub(crate) struct FetchResponse {
+    status: u16,
+    final_url: String,
+    headers: HashMap<String, String>,
+    pub(crate) body: String,
+    cached: bool,
+}
+
+// Shared fetch logic used by the one-shot commands, the polling scheduler, and the parsing
+// subsystem.
+pub(crate) async fn fetch(url: &str, headers: Option<HashMap<String, String>>) -> Result<FetchResponse, String> {
     // Create a new HTTP client
     let client = reqwest::Client::new();
-    
+
     // Set up headers to make our request look like it's coming from a web browser
-    let mut headers = HeaderMap::new();
-    headers.insert(USER_AGENT, HeaderValue::from_static(
+    let mut header_map = HeaderMap::new();
+    header_map.insert(USER_AGENT, HeaderValue::from_static(
         "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36"
     ));
-    
+
+    // Merge in any caller-supplied headers, overriding the defaults above where they collide
+    merge_headers(&mut header_map, headers)?;
+
     // Make the HTTP request with our configured headers
     let response = client
         .get(url)
-        .headers(headers)
+        .headers(header_map)
         .send()
         .await
         .map_err(|e| e.to_string())?;
-        
+
+    let status = response.status().as_u16();
+    let final_url = response.url().to_string();
+    let response_headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+        .collect();
+
     // Get the response text
-    let text = response
+    let body = response
         .text()
         .await
         .map_err(|e| e.to_string())?;
-        
-    Ok(text)
+
+    Ok(FetchResponse {
+        status,
+        final_url,
+        headers: response_headers,
+        body,
+        cached: false,
+    })
+}
+
+// A cached response, plus the validators needed to make a conditional request next time.
+struct CacheEntry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: Instant,
+    response: FetchResponse,
+}
+
+// Caches fetches keyed by (URL, request identity), so the app can poll bookmaker pages without
+// re-downloading unchanged content on every tick.
+#[derive(Default)]
+pub(crate) struct CacheState(Mutex<HashMap<String, CacheEntry>>);
+
+// Two requests are only the same cache entry if they'd hit the server identically — same URL
+// *and* same headers (which is where a resolved `auth_secret` ends up, as an Authorization
+// header). Otherwise one account's authenticated response could be served back for another
+// credential, or for an unauthenticated caller, until the TTL expires.
+fn cache_key(url: &str, headers: &HashMap<String, String>) -> String {
+    let mut pairs: Vec<(&String, &String)> = headers.iter().collect();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    let identity = pairs
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("\u{1e}");
+
+    format!("{}\u{1e}{}", url, identity)
+}
+
+// Adds `If-None-Match`/`If-Modified-Since` from a prior cached response's validators, without
+// overriding anything the caller already set explicitly.
+fn add_revalidation_headers(
+    mut headers: HashMap<String, String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+) -> HashMap<String, String> {
+    // Header names are case-insensitive, so a caller's "if-none-match" counts as set too.
+    let caller_set = |headers: &HashMap<String, String>, name: &str| {
+        headers.keys().any(|key| key.eq_ignore_ascii_case(name))
+    };
+    if let Some(etag) = etag {
+        if !caller_set(&headers, "If-None-Match") {
+            headers.insert("If-None-Match".to_string(), etag);
+        }
+    }
+    if let Some(last_modified) = last_modified {
+        if !caller_set(&headers, "If-Modified-Since") {
+            headers.insert("If-Modified-Since".to_string(), last_modified);
+        }
+    }
+
+    headers
+}
+
+// Serves a fetch from cache when `ttl_secs` hasn't elapsed, otherwise re-requests with
+// `If-None-Match`/`If-Modified-Since` and falls back to the cached copy on a 304.
+pub(crate) async fn fetch_cached(
+    url: &str,
+    headers: Option<HashMap<String, String>>,
+    ttl_secs: Option<u64>,
+    cache: &CacheState,
+) -> Result<FetchResponse, String> {
+    let headers = headers.unwrap_or_default();
+    let key = cache_key(url, &headers);
+
+    if let Some(ttl) = ttl_secs.map(Duration::from_secs) {
+        let entries = cache.0.lock().map_err(|e| e.to_string())?;
+        if let Some(entry) = entries.get(&key) {
+            if entry.fetched_at.elapsed() < ttl {
+                let mut response = entry.response.clone();
+                response.cached = true;
+                return Ok(response);
+            }
+        }
+    }
+
+    let (etag, last_modified) = {
+        let entries = cache.0.lock().map_err(|e| e.to_string())?;
+        entries
+            .get(&key)
+            .map(|entry| (entry.etag.clone(), entry.last_modified.clone()))
+            .unwrap_or((None, None))
+    };
+
+    let request_headers = add_revalidation_headers(headers, etag, last_modified);
+
+    let response = fetch(url, Some(request_headers)).await?;
+    let mut entries = cache.0.lock().map_err(|e| e.to_string())?;
+
+    if response.status == 304 {
+        let entry = entries
+            .get_mut(&key)
+            .ok_or_else(|| "received 304 Not Modified with no cached entry".to_string())?;
+        entry.fetched_at = Instant::now();
+        let mut cached_response = entry.response.clone();
+        cached_response.cached = true;
+        return Ok(cached_response);
+    }
+
+    entries.insert(
+        key,
+        CacheEntry {
+            etag: response.headers.get("etag").cloned(),
+            last_modified: response.headers.get("last-modified").cloned(),
+            fetched_at: Instant::now(),
+            response: response.clone(),
+        },
+    );
+
+    Ok(response)
+}
+
+// Resolves `auth_secret` by name, if given, into an Authorization header so the raw key only
+// ever crosses the IPC boundary once, when it was stored. The keychain lookup is blocking, so it
+// runs off the async runtime's worker thread via `secret::resolve_blocking`.
+pub(crate) async fn apply_auth_secret(
+    mut headers: HashMap<String, String>,
+    auth_secret: Option<String>,
+) -> HashMap<String, String> {
+    if let Some(name) = auth_secret {
+        if let Some(value) = secret::resolve_blocking(name).await {
+            headers.entry("Authorization".to_string()).or_insert(value);
+        }
+    }
+
+    headers
+}
+
+// This function will be callable from our frontend JavaScript
+#[command]
+async fn fetch_odds_detailed(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    ttl_secs: Option<u64>,
+    auth_secret: Option<String>,
+    cache: State<'_, CacheState>,
+) -> Result<FetchResponse, String> {
+    let headers = apply_auth_secret(headers.unwrap_or_default(), auth_secret).await;
+    fetch_cached(&url, Some(headers), ttl_secs, &cache).await
+}
+
+// Kept for backward compatibility with callers that only want the raw body.
+#[command]
+async fn fetch_odds(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    ttl_secs: Option<u64>,
+    auth_secret: Option<String>,
+    cache: State<'_, CacheState>,
+) -> Result<String, String> {
+    let headers = apply_auth_secret(headers.unwrap_or_default(), auth_secret).await;
+    fetch_cached(&url, Some(headers), ttl_secs, &cache).await.map(|r| r.body)
+}
+
+// Drops every cached entry for `url` (there may be more than one, keyed by request identity),
+// or every cached entry altogether when `url` is `None`.
+#[command]
+fn clear_cache(url: Option<String>, cache: State<CacheState>) -> Result<(), String> {
+    let mut entries = cache.0.lock().map_err(|e| e.to_string())?;
+
+    match url {
+        Some(url) => {
+            let prefix = format!("{}\u{1e}", url);
+            entries.retain(|key, _| !key.starts_with(&prefix));
+        }
+        None => entries.clear(),
+    }
+
+    Ok(())
+}
+
+// Merges a caller-supplied header map into `header_map`, surfacing invalid names/values as
+// descriptive errors instead of letting the HeaderName/HeaderValue parsing panic.
+fn merge_headers(header_map: &mut HeaderMap, headers: Option<HashMap<String, String>>) -> Result<(), String> {
+    let Some(headers) = headers else {
+        return Ok(());
+    };
+
+    for (name, value) in headers {
+        let header_name = HeaderName::from_str(&name)
+            .map_err(|e| format!("invalid header name \"{}\": {}", name, e))?;
+        let header_value = HeaderValue::from_str(&value)
+            .map_err(|e| format!("invalid header value for \"{}\": {}", name, e))?;
+        header_map.insert(header_name, header_value);
+    }
+
+    Ok(())
+}
+
+// One entry per URL currently being polled, so `stop_polling` can abort the right task.
+#[derive(Default)]
+struct PollingState(Mutex<HashMap<String, JoinHandle<()>>>);
+
+// Payload emitted on the "odds-update" event every time a polled URL is re-fetched.
+#[derive(Clone, Serialize)]
+struct OddsUpdate {
+    url: String,
+    status: u16,
+    body: String,
+    fetched_at: u64,
+}
+
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Starts a background task that re-fetches `url` every `interval_secs` and emits an
+// "odds-update" event with the latest status/body. Replaces any existing poll for the same URL.
+// `headers`/`auth_secret` are resolved once up front, the same way `fetch_odds_detailed` does,
+// so polling can reach header-gated pages and authenticated feeds rather than only open ones;
+// `ttl_secs` is threaded through to `fetch_cached` so repeated polls can ride the
+// ETag/Last-Modified cache instead of always re-downloading.
+#[command]
+async fn start_polling(
+    url: String,
+    interval_secs: u64,
+    headers: Option<HashMap<String, String>>,
+    auth_secret: Option<String>,
+    ttl_secs: Option<u64>,
+    app_handle: AppHandle,
+    state: State<'_, PollingState>,
+) -> Result<(), String> {
+    if interval_secs == 0 {
+        return Err("interval_secs must be greater than 0".to_string());
+    }
+
+    let headers = apply_auth_secret(headers.unwrap_or_default(), auth_secret).await;
+
+    let mut tasks = state.0.lock().map_err(|e| e.to_string())?;
+
+    if let Some(existing) = tasks.remove(&url) {
+        existing.abort();
+    }
+
+    let task_url = url.clone();
+    let task_app_handle = app_handle.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+
+            let cache = task_app_handle.state::<CacheState>();
+            match fetch_cached(&task_url, Some(headers.clone()), ttl_secs, &cache).await {
+                Ok(response) => {
+                    let _ = task_app_handle.emit_all(
+                        "odds-update",
+                        OddsUpdate {
+                            url: task_url.clone(),
+                            status: response.status,
+                            body: response.body,
+                            fetched_at: unix_timestamp(),
+                        },
+                    );
+                }
+                Err(_) => continue,
+            }
+        }
+    });
+
+    tasks.insert(url, handle);
+    Ok(())
+}
+
+// Aborts the polling task for `url`, if one is running, and removes it from the managed state.
+#[command]
+fn stop_polling(url: String, state: State<PollingState>) -> Result<(), String> {
+    let mut tasks = state.0.lock().map_err(|e| e.to_string())?;
+
+    if let Some(handle) = tasks.remove(&url) {
+        handle.abort();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_headers_rejects_invalid_header_name() {
+        let mut header_map = HeaderMap::new();
+        let mut headers = HashMap::new();
+        headers.insert("Invalid Header".to_string(), "value".to_string());
+
+        let err = merge_headers(&mut header_map, Some(headers)).unwrap_err();
+        assert!(err.contains("invalid header name"));
+    }
+
+    #[test]
+    fn merge_headers_rejects_invalid_header_value() {
+        let mut header_map = HeaderMap::new();
+        let mut headers = HashMap::new();
+        headers.insert("X-Custom".to_string(), "bad\nvalue".to_string());
+
+        let err = merge_headers(&mut header_map, Some(headers)).unwrap_err();
+        assert!(err.contains("invalid header value"));
+    }
+
+    #[test]
+    fn merge_headers_overrides_defaults() {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(USER_AGENT, HeaderValue::from_static("default-ua"));
+
+        let mut headers = HashMap::new();
+        headers.insert("User-Agent".to_string(), "custom-ua".to_string());
+
+        merge_headers(&mut header_map, Some(headers)).unwrap();
+        assert_eq!(header_map.get(USER_AGENT).unwrap(), "custom-ua");
+    }
+
+    #[test]
+    fn cache_key_differs_by_auth_header() {
+        let mut unauthenticated = HashMap::new();
+        unauthenticated.insert("User-Agent".to_string(), "test".to_string());
+
+        let mut account_a = unauthenticated.clone();
+        account_a.insert("Authorization".to_string(), "bookAuth".to_string());
+
+        let mut account_b = unauthenticated.clone();
+        account_b.insert("Authorization".to_string(), "bookBuser".to_string());
+
+        let url = "https://example.com/odds";
+        let key_none = cache_key(url, &unauthenticated);
+        let key_a = cache_key(url, &account_a);
+        let key_b = cache_key(url, &account_b);
+
+        assert_ne!(key_none, key_a);
+        assert_ne!(key_none, key_b);
+        assert_ne!(key_a, key_b);
+    }
+
+    #[test]
+    fn cache_key_is_order_independent() {
+        let mut headers_one = HashMap::new();
+        headers_one.insert("A".to_string(), "1".to_string());
+        headers_one.insert("B".to_string(), "2".to_string());
+
+        let mut headers_two = HashMap::new();
+        headers_two.insert("B".to_string(), "2".to_string());
+        headers_two.insert("A".to_string(), "1".to_string());
+
+        assert_eq!(
+            cache_key("https://example.com", &headers_one),
+            cache_key("https://example.com", &headers_two)
+        );
+    }
+
+    #[test]
+    fn add_revalidation_headers_does_not_override_caller_headers() {
+        let mut headers = HashMap::new();
+        headers.insert("If-None-Match".to_string(), "caller-etag".to_string());
+
+        let headers = add_revalidation_headers(
+            headers,
+            Some("cached-etag".to_string()),
+            Some("cached-last-modified".to_string()),
+        );
+
+        assert_eq!(headers.get("If-None-Match").unwrap(), "caller-etag");
+        assert_eq!(
+            headers.get("If-Modified-Since").unwrap(),
+            "cached-last-modified"
+        );
+    }
+
+    #[test]
+    fn add_revalidation_headers_is_noop_without_validators() {
+        let headers = add_revalidation_headers(HashMap::new(), None, None);
+        assert!(headers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_cached_serves_cached_response_within_ttl() {
+        let cache = CacheState::default();
+        let headers = HashMap::new();
+        let key = cache_key("https://example.com/odds", &headers);
+
+        cache.0.lock().unwrap().insert(
+            key,
+            CacheEntry {
+                etag: None,
+                last_modified: None,
+                fetched_at: Instant::now(),
+                response: FetchResponse {
+                    status: 200,
+                    final_url: "https://example.com/odds".to_string(),
+                    headers: HashMap::new(),
+                    body: "cached-body".to_string(),
+                    cached: false,
+                },
+            },
+        );
+
+        let response = fetch_cached("https://example.com/odds", Some(headers), Some(60), &cache)
+            .await
+            .unwrap();
+
+        assert!(response.cached);
+        assert_eq!(response.body, "cached-body");
+    }
 }
 
 fn main() {
     // Set up the Tauri application with our fetch_odds command
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![fetch_odds])
+        .manage(PollingState::default())
+        .manage(CacheState::default())
+        .manage(listener::ListenerState::default())
+        .invoke_handler(tauri::generate_handler![
+            fetch_odds,
+            fetch_odds_detailed,
+            start_polling,
+            stop_polling,
+            clear_cache,
+            secret::store_secret,
+            secret::get_secret,
+            secret::delete_secret,
+            listener::start_listener,
+            listener::stop_listener,
+            parse::extract_odds
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+// Secure on-disk storage for per-bookmaker API keys and credentials, so raw secrets never
+// live in the frontend bundle and only cross the IPC boundary once, at the moment they're stored.
+use keyring::Entry;
+use tauri::command;
+
+const SERVICE: &str = "odds-tracker-desktop";
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, name).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn store_secret(name: String, value: String) -> Result<(), String> {
+    entry(&name)?.set_password(&value).map_err(|e| e.to_string())
+}
+
+#[command]
+pub fn get_secret(name: String) -> Option<String> {
+    entry(&name).ok()?.get_password().ok()
+}
+
+#[command]
+pub fn delete_secret(name: String) -> Result<(), String> {
+    entry(&name)?.delete_password().map_err(|e| e.to_string())
+}
+
+// Resolves a stored secret by name for use directly in a request (e.g. as an Authorization
+// header), without routing the value back through the frontend.
+fn resolve(name: &str) -> Option<String> {
+    entry(name).ok()?.get_password().ok()
+}
+
+// Same as `resolve`, but off the async runtime's worker thread — the underlying OS keychain call
+// is blocking (DBus/libsecret on Linux, Keychain Services on macOS, and may prompt for unlock),
+// so callers inside `async fn` command bodies must not call `resolve` directly.
+pub async fn resolve_blocking(name: String) -> Option<String> {
+    tauri::async_runtime::spawn_blocking(move || resolve(&name))
+        .await
+        .ok()
+        .flatten()
+}